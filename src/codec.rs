@@ -0,0 +1,427 @@
+use std::{
+    fmt::{self, Display},
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use async_trait::async_trait;
+use futures_lite::Stream;
+
+use crate::{
+    consumer::{IncomingMessage, MessageBus},
+    message::RawHeaders,
+    producer::Producer,
+};
+
+const CONTENT_TYPE_HEADER: &str = "content-type";
+
+/// (De)serializes a payload of type `T` to and from the raw bytes a
+/// [`Producer`]/[`IncomingMessage`] deals in, so call sites stop
+/// re-implementing serialization and content-type negotiation themselves.
+pub trait Codec<T> {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// The content type this codec encodes/decodes, stamped on outgoing
+    /// messages by [`TypedProducer::send`] and checked against incoming
+    /// messages by [`TypedMessage::decode`].
+    fn content_type(&self) -> &'static str;
+
+    /// Encodes `value`, returning the payload bytes and the content type
+    /// to stamp on the outgoing message.
+    fn encode(&self, value: &T) -> Result<(Vec<u8>, &'static str), Self::Error>;
+
+    /// Decodes `payload` into `T`.
+    fn decode(&self, payload: &[u8]) -> Result<T, Self::Error>;
+}
+
+#[cfg(feature = "json")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonCodec;
+
+#[cfg(feature = "json")]
+impl<T> Codec<T> for JsonCodec
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    type Error = serde_json::Error;
+
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn encode(&self, value: &T) -> Result<(Vec<u8>, &'static str), Self::Error> {
+        let payload = serde_json::to_vec(value)?;
+
+        Ok((payload, self.content_type()))
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<T, Self::Error> {
+        serde_json::from_slice(payload)
+    }
+}
+
+#[cfg(feature = "protobuf")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProtobufCodec;
+
+#[cfg(feature = "protobuf")]
+impl<T> Codec<T> for ProtobufCodec
+where
+    T: prost::Message + Default,
+{
+    type Error = prost::DecodeError;
+
+    fn content_type(&self) -> &'static str {
+        "application/protobuf"
+    }
+
+    fn encode(&self, value: &T) -> Result<(Vec<u8>, &'static str), Self::Error> {
+        Ok((value.encode_to_vec(), self.content_type()))
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<T, Self::Error> {
+        T::decode(payload)
+    }
+}
+
+/// Wraps a [`Producer`] to encode `T` values with `C` instead of callers
+/// building `Vec<u8>` payloads and content-type headers by hand.
+pub struct TypedProducer<P, C, T> {
+    inner: P,
+    codec: C,
+    _payload: PhantomData<fn(T)>,
+}
+
+impl<P, C, T> TypedProducer<P, C, T>
+where
+    P: Producer,
+    C: Codec<T>,
+{
+    pub fn new(inner: P, codec: C) -> Self {
+        Self {
+            inner,
+            codec,
+            _payload: PhantomData,
+        }
+    }
+
+    pub async fn send(
+        &self,
+        key: String,
+        mut headers: RawHeaders,
+        value: &T,
+        options: P::Options,
+    ) -> Result<(), SendError<C::Error, P::Error>> {
+        let (payload, content_type) = self.codec.encode(value).map_err(SendError::Encode)?;
+        headers.insert(CONTENT_TYPE_HEADER.to_string(), content_type.to_string());
+
+        self.inner
+            .send(key, headers, payload, options)
+            .await
+            .map_err(SendError::Send)
+    }
+}
+
+/// Error returned by [`TypedProducer::send`]: either `value` failed to
+/// encode, or the encoded payload failed to send.
+#[derive(Debug)]
+pub enum SendError<E, S> {
+    Encode(E),
+    Send(S),
+}
+
+impl<E: Display, S: Display> Display for SendError<E, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(err) => write!(f, "failed to encode message: {err}"),
+            Self::Send(err) => write!(f, "failed to send message: {err}"),
+        }
+    }
+}
+
+impl<E, S> std::error::Error for SendError<E, S>
+where
+    E: std::error::Error + 'static,
+    S: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Encode(err) => Some(err),
+            Self::Send(err) => Some(err),
+        }
+    }
+}
+
+/// Wraps an [`IncomingMessage`] so its payload is decoded as `T` via `C`
+/// instead of callers re-implementing deserialization at every call site.
+///
+/// All [`IncomingMessage`] methods, [`payload`](IncomingMessage::payload)
+/// included, delegate to the wrapped message unchanged; use
+/// [`decode`](Self::decode) to get at the typed value.
+pub struct TypedMessage<M, C, T> {
+    inner: M,
+    codec: C,
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<M, C, T> TypedMessage<M, C, T>
+where
+    M: IncomingMessage,
+    C: Codec<T>,
+{
+    pub fn new(inner: M, codec: C) -> Self {
+        Self {
+            inner,
+            codec,
+            _payload: PhantomData,
+        }
+    }
+
+    /// Decodes the message's payload as `T`.
+    ///
+    /// Checks the message's `content-type` header against `C` first, so a
+    /// topic carrying more than one content type (or schema version) is
+    /// rejected with [`DecodeError::ContentTypeMismatch`] instead of being
+    /// run through the wrong codec.
+    ///
+    /// On a content-type mismatch or decode failure, rejects the
+    /// underlying message — forwarding it to the dead-letter topic if one
+    /// is configured — instead of panicking. The reject itself is
+    /// best-effort: its result is swallowed so a broken connection
+    /// doesn't mask the original error.
+    pub async fn decode(&self) -> Result<T, DecodeError<C::Error>> {
+        let expected = self.codec.content_type();
+        let found = self.inner.headers().get(CONTENT_TYPE_HEADER).cloned();
+
+        if found.as_deref() != Some(expected) {
+            let _ = self.inner.reject().await;
+            return Err(DecodeError::ContentTypeMismatch { expected, found });
+        }
+
+        match self.codec.decode(self.inner.payload()) {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                let _ = self.inner.reject().await;
+                Err(DecodeError::Codec(err))
+            }
+        }
+    }
+}
+
+/// Error returned by [`TypedMessage::decode`]: either the message's
+/// `content-type` header didn't match what `C` expects, or the payload
+/// failed to decode.
+#[derive(Debug)]
+pub enum DecodeError<E> {
+    ContentTypeMismatch {
+        expected: &'static str,
+        found: Option<String>,
+    },
+    Codec(E),
+}
+
+impl<E: Display> Display for DecodeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContentTypeMismatch { expected, found } => {
+                write!(f, "expected content-type {expected:?}, found {found:?}")
+            }
+            Self::Codec(err) => write!(f, "failed to decode message: {err}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for DecodeError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ContentTypeMismatch { .. } => None,
+            Self::Codec(err) => Some(err),
+        }
+    }
+}
+
+#[async_trait]
+impl<M, C, T> IncomingMessage for TypedMessage<M, C, T>
+where
+    M: IncomingMessage,
+    C: Codec<T> + Send + Sync,
+    T: Send,
+{
+    type Error = M::Error;
+
+    fn headers(&self) -> RawHeaders {
+        self.inner.headers()
+    }
+
+    fn payload(&self) -> &[u8] {
+        self.inner.payload()
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        self.inner.key()
+    }
+
+    async fn ack(&self) -> Result<(), Self::Error> {
+        self.inner.ack().await
+    }
+
+    async fn nack(&self) -> Result<(), Self::Error> {
+        self.inner.nack().await
+    }
+
+    async fn reject(&self) -> Result<(), Self::Error> {
+        self.inner.reject().await
+    }
+
+    fn make_span(&self) -> tracing::Span {
+        self.inner.make_span()
+    }
+}
+
+/// Wraps a [`MessageBus`] so every yielded message is a [`TypedMessage`]
+/// instead of a raw payload.
+pub struct TypedConsumer<M, C, T> {
+    inner: M,
+    codec: C,
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<M, C, T> TypedConsumer<M, C, T> {
+    pub fn new(inner: M, codec: C) -> Self {
+        Self {
+            inner,
+            codec,
+            _payload: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<M, C, T> MessageBus for TypedConsumer<M, C, T>
+where
+    M: MessageBus + Send,
+    C: Codec<T> + Clone + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    type IncomingMessage = TypedMessage<M::IncomingMessage, C, T>;
+    type Error = M::Error;
+    type Stream = TypedMessageStream<M::Stream, C, T>;
+
+    async fn into_stream(self) -> Result<Self::Stream, Self::Error> {
+        let stream = self.inner.into_stream().await?;
+
+        Ok(TypedMessageStream {
+            inner: stream,
+            codec: self.codec,
+            _payload: PhantomData,
+        })
+    }
+}
+
+pub struct TypedMessageStream<S, C, T> {
+    inner: S,
+    codec: C,
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<S, C, T, Msg, Err> Stream for TypedMessageStream<S, C, T>
+where
+    S: Stream<Item = Result<Msg, Err>> + Unpin,
+    Msg: IncomingMessage,
+    C: Codec<T> + Clone,
+{
+    type Item = Result<TypedMessage<Msg, C, T>, Err>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let codec = self.codec.clone();
+
+        Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map_ok(|message| TypedMessage::new(message, codec))
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::sync::Arc;
+
+    use futures_lite::{future::block_on, StreamExt};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::integration::local::{
+        LocalBroker, LocalConsumer, LocalProducer, LocalProducerOptions,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Widget {
+        name: String,
+    }
+
+    #[test]
+    fn round_trips_through_the_configured_codec() {
+        let broker = LocalBroker::new();
+        let producer = TypedProducer::new(
+            LocalProducer::new(Arc::clone(&broker), "widgets"),
+            JsonCodec,
+        );
+
+        block_on(producer.send(
+            "key".to_string(),
+            RawHeaders::default(),
+            &Widget {
+                name: "sprocket".to_string(),
+            },
+            LocalProducerOptions::default(),
+        ))
+        .unwrap();
+
+        let consumer = TypedConsumer::new(
+            LocalConsumer::new(Arc::clone(&broker), "widgets", "group"),
+            JsonCodec,
+        );
+        let mut stream = block_on(consumer.into_stream()).unwrap();
+        let message = block_on(stream.next()).unwrap().unwrap();
+
+        let widget = block_on(message.decode()).unwrap();
+        assert_eq!(
+            widget,
+            Widget {
+                name: "sprocket".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_messages_with_a_mismatched_content_type() {
+        let broker = LocalBroker::new();
+
+        // Bypass `TypedProducer` so the message carries no `content-type`
+        // header at all.
+        let payload = serde_json::to_vec(&Widget {
+            name: "sprocket".to_string(),
+        })
+        .unwrap();
+        block_on(LocalProducer::new(Arc::clone(&broker), "widgets").send(
+            "key".to_string(),
+            RawHeaders::default(),
+            payload,
+            LocalProducerOptions::default(),
+        ))
+        .unwrap();
+
+        let consumer = TypedConsumer::new(
+            LocalConsumer::new(Arc::clone(&broker), "widgets", "group"),
+            JsonCodec,
+        );
+        let mut stream = block_on(consumer.into_stream()).unwrap();
+        let message = block_on(stream.next()).unwrap().unwrap();
+
+        let err = block_on(message.decode()).unwrap_err();
+        assert!(matches!(
+            err,
+            DecodeError::ContentTypeMismatch { found: None, .. }
+        ));
+    }
+}
@@ -1,25 +1,32 @@
 use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
     fmt::Display,
     mem::{self, ManuallyDrop},
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
 use futures_lite::{Stream, StreamExt};
+use opentelemetry::propagation::{Extractor, Injector, TextMapPropagator};
 use rdkafka::{
     consumer::{CommitMode, Consumer, ConsumerContext, MessageStream, StreamConsumer},
     error::KafkaError,
-    message::{BorrowedMessage, Headers, Message as _Message, OwnedHeaders},
+    message::{BorrowedMessage, Header, Headers, Message as _Message, OwnedHeaders},
     producer::{FutureProducer, FutureRecord},
     ClientContext,
 };
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::{
     consumer::{IncomingMessage, MessageBus},
     message::RawHeaders,
+    metrics::{Metrics, NoopMetrics, Outcome},
     producer::Producer,
 };
 
@@ -29,13 +36,29 @@ where
 {
     consumer: ManuallyDrop<Arc<StreamConsumer<C>>>,
     stream: ManuallyDrop<MessageStream<'static>>,
+    dead_letter: Option<Arc<DeadLetterProducer<C>>>,
+    reported_rate_limit: bool,
+    commit: Option<Arc<BatchCommitState>>,
+    metrics: Arc<dyn Metrics>,
+    // Held only so its `Drop` stops the sampling thread when the stream
+    // does; never read otherwise.
+    #[allow(dead_code)]
+    lag_sampler: Option<LagSampler>,
 }
 
 impl<C: ConsumerContext> RdKafkaMessageStream<C> {
     /// Constructs new `RdKafkaMessageStream`
     ///
     /// SAFETY: `stream` must originate from `consumer`
-    unsafe fn new<'a>(consumer: &'a Arc<StreamConsumer<C>>, stream: MessageStream<'a>) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn new<'a>(
+        consumer: &'a Arc<StreamConsumer<C>>,
+        stream: MessageStream<'a>,
+        dead_letter: Option<Arc<DeadLetterProducer<C>>>,
+        commit: Option<Arc<BatchCommitState>>,
+        metrics: Arc<dyn Metrics>,
+        lag_sampler: Option<LagSampler>,
+    ) -> Self {
         let consumer = Arc::clone(consumer);
 
         let stream = mem::transmute::<_, MessageStream<'static>>(stream);
@@ -43,12 +66,94 @@ impl<C: ConsumerContext> RdKafkaMessageStream<C> {
         Self {
             consumer: ManuallyDrop::new(consumer),
             stream: ManuallyDrop::new(stream),
+            dead_letter,
+            reported_rate_limit: false,
+            commit,
+            metrics,
+            lag_sampler,
         }
     }
 }
 
+/// Best-effort per-partition lag (`high_watermark - offset`) sample,
+/// reported through `metrics`. Uses a short fetch timeout so a slow broker
+/// cannot stall the dedicated sampling thread for long.
+fn sample_lag<C: ConsumerContext>(consumer: &StreamConsumer<C>, metrics: &dyn Metrics) {
+    let Ok(assignment) = consumer.assignment() else {
+        return;
+    };
+
+    let Ok(position) = consumer.position() else {
+        return;
+    };
+
+    for element in assignment.elements() {
+        let topic = element.topic();
+        let partition = element.partition();
+
+        let Some(offset) = position
+            .find_partition(topic, partition)
+            .and_then(|p| p.offset().to_raw())
+        else {
+            continue;
+        };
+
+        let Ok((_, high_watermark)) =
+            consumer.fetch_watermarks(topic, partition, Duration::from_millis(200))
+        else {
+            continue;
+        };
+
+        metrics.consumer_lag(topic, partition, high_watermark - offset);
+    }
+}
+
+/// Drives periodic consumer-lag sampling on a dedicated OS thread, since
+/// `fetch_watermarks` (and, to a lesser extent, `assignment`/`position`) are
+/// blocking librdkafka calls that must not run on the stream's poll path.
+struct LagSampler {
+    stop: Arc<AtomicBool>,
+}
+
+impl LagSampler {
+    fn spawn<C: ConsumerContext + 'static>(
+        consumer: Arc<StreamConsumer<C>>,
+        metrics: Arc<dyn Metrics>,
+        interval: Duration,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_signal = Arc::clone(&stop);
+
+        std::thread::spawn(move || {
+            while !stop_signal.load(Ordering::SeqCst) {
+                std::thread::sleep(interval);
+
+                if stop_signal.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                sample_lag(&consumer, metrics.as_ref());
+            }
+        });
+
+        Self { stop }
+    }
+}
+
+impl Drop for LagSampler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
 impl<C: ConsumerContext> Drop for RdKafkaMessageStream<C> {
     fn drop(&mut self) {
+        // Flush any offsets still only held in the local store so a
+        // batched-mode consumer doesn't lose progress on shutdown.
+        if self.commit.is_some() {
+            let _ = self.consumer.commit_consumer_state(CommitMode::Sync);
+        }
+
         // SAFETY: By preserving order (stream first, consumer second)
         // we guarantee that `message` still points to valid memory
         // allocated by rdkafka
@@ -60,12 +165,34 @@ impl<C: ConsumerContext> Drop for RdKafkaMessageStream<C> {
 }
 
 impl<C: ConsumerContext> Stream for RdKafkaMessageStream<C> {
-    type Item = Result<RdKafkaOwnedMessage<C>, KafkaError>;
+    type Item = Result<RdKafkaOwnedMessage<C>, KafkaConsumerError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(dead_letter) = self.dead_letter.as_ref() {
+            if dead_letter.is_tripped() {
+                if self.reported_rate_limit {
+                    return Poll::Ready(None);
+                }
+
+                self.reported_rate_limit = true;
+                return Poll::Ready(Some(Err(KafkaConsumerError::DeadLetterRateExceeded)));
+            }
+        }
+
+        let dead_letter = self.dead_letter.clone();
+        let commit = self.commit.clone();
+        let metrics = Arc::clone(&self.metrics);
+
         self.stream
             .poll_next(cx)
-            .map_ok(|message| unsafe { RdKafkaOwnedMessage::new(&self.consumer, message) })
+            .map_ok(|message| {
+                metrics.message_received(message.topic());
+
+                unsafe {
+                    RdKafkaOwnedMessage::new(&self.consumer, message, dead_letter, commit, metrics)
+                }
+            })
+            .map_err(KafkaConsumerError::Kafka)
     }
 }
 
@@ -75,13 +202,22 @@ where
 {
     consumer: ManuallyDrop<Arc<StreamConsumer<C>>>,
     message: ManuallyDrop<BorrowedMessage<'static>>,
+    dead_letter: Option<Arc<DeadLetterProducer<C>>>,
+    commit: Option<Arc<BatchCommitState>>,
+    metrics: Arc<dyn Metrics>,
 }
 
 impl<C: ConsumerContext> RdKafkaOwnedMessage<C> {
     /// Constructs new `RdkafkaOwnedMessage`
     ///
     /// SAFETY: `message` must originate from `consumer`
-    unsafe fn new<'a>(consumer: &'a Arc<StreamConsumer<C>>, message: BorrowedMessage<'a>) -> Self {
+    unsafe fn new<'a>(
+        consumer: &'a Arc<StreamConsumer<C>>,
+        message: BorrowedMessage<'a>,
+        dead_letter: Option<Arc<DeadLetterProducer<C>>>,
+        commit: Option<Arc<BatchCommitState>>,
+        metrics: Arc<dyn Metrics>,
+    ) -> Self {
         let consumer = Arc::clone(consumer);
 
         // SAFETY: since we have `consumer` for 'static we can extend
@@ -91,6 +227,9 @@ impl<C: ConsumerContext> RdKafkaOwnedMessage<C> {
         Self {
             consumer: ManuallyDrop::new(consumer),
             message: ManuallyDrop::new(message),
+            dead_letter,
+            commit,
+            metrics,
         }
     }
 
@@ -102,6 +241,33 @@ impl<C: ConsumerContext> RdKafkaOwnedMessage<C> {
         self.consumer
             .commit_message(&self.message, CommitMode::Async)
     }
+
+    /// Commits or locally stores-and-conditionally-flushes this message's
+    /// offset, depending on the consumer's configured [`CommitConfig`].
+    /// Shared by `ack`, `reject`, and a `nack` whose message was forwarded
+    /// to the dead-letter topic, which all advance the partition past this
+    /// message.
+    fn settle_offset(&self) -> Result<(), KafkaError> {
+        let Some(batch) = self.commit.as_deref() else {
+            return self
+                .consumer
+                .commit_message(&self.message, CommitMode::Async);
+        };
+
+        let msg = self.message();
+
+        if let Some(offset) = batch.ack(msg.topic(), msg.partition(), msg.offset()) {
+            self.consumer
+                .store_offset(msg.topic(), msg.partition(), offset + 1)?;
+        }
+
+        if batch.should_flush() {
+            self.consumer.commit_consumer_state(CommitMode::Async)?;
+            batch.reset();
+        }
+
+        Ok(())
+    }
 }
 
 impl<C: ConsumerContext> Drop for RdKafkaOwnedMessage<C> {
@@ -116,11 +282,333 @@ impl<C: ConsumerContext> Drop for RdKafkaOwnedMessage<C> {
     }
 }
 
+const DLQ_ORIGINAL_TOPIC_HEADER: &str = "convoy.dlq.original_topic";
+const DLQ_ORIGINAL_PARTITION_HEADER: &str = "convoy.dlq.original_partition";
+const DLQ_ORIGINAL_OFFSET_HEADER: &str = "convoy.dlq.original_offset";
+const DLQ_REASON_HEADER: &str = "convoy.dlq.reason";
+const DLQ_RETRY_COUNT_HEADER: &str = "convoy.dlq.retry_count";
+
+/// Configuration for the dead-letter topic a [`KafkaConsumer`] forwards
+/// unprocessable messages to, and for the invalid-message-rate policy that
+/// stops the stream instead of flooding that topic under a systemic
+/// failure.
+#[derive(Debug, Clone)]
+pub struct DeadLetterConfig {
+    topic: String,
+    max_failures: usize,
+    window: Duration,
+}
+
+impl DeadLetterConfig {
+    /// Forward rejected/nacked messages to `topic`, tripping the
+    /// invalid-message-rate policy once more than `max_failures` messages
+    /// are rejected within `window`.
+    pub fn new(topic: impl Into<String>, max_failures: usize, window: Duration) -> Self {
+        Self {
+            topic: topic.into(),
+            max_failures,
+            window,
+        }
+    }
+}
+
+/// Tracks reject/nack timestamps in a sliding window and trips once more
+/// than `max_failures` fall within `window`.
+struct FailureWindow {
+    max_failures: usize,
+    window: Duration,
+    failures: Mutex<VecDeque<Instant>>,
+    tripped: AtomicBool,
+}
+
+impl FailureWindow {
+    fn new(max_failures: usize, window: Duration) -> Self {
+        Self {
+            max_failures,
+            window,
+            failures: Mutex::new(VecDeque::new()),
+            tripped: AtomicBool::new(false),
+        }
+    }
+
+    /// Records a failure, returning `true` if the policy is tripped
+    /// (either by this failure or a previous one).
+    fn record(&self) -> bool {
+        let now = Instant::now();
+
+        let mut failures = self.failures.lock().unwrap();
+        failures.push_back(now);
+
+        while let Some(&oldest) = failures.front() {
+            if now.duration_since(oldest) > self.window {
+                failures.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if failures.len() > self.max_failures {
+            self.tripped.store(true, Ordering::SeqCst);
+        }
+
+        self.tripped.load(Ordering::SeqCst)
+    }
+
+    fn is_tripped(&self) -> bool {
+        self.tripped.load(Ordering::SeqCst)
+    }
+}
+
+/// Republishes messages rejected/nacked by a [`KafkaConsumer`] to a
+/// dead-letter topic, enforcing an invalid-message-rate policy so a
+/// systemic failure stops the stream instead of flooding that topic.
+pub struct DeadLetterProducer<C: ClientContext + 'static> {
+    producer: FutureProducer<C>,
+    topic: String,
+    failures: FailureWindow,
+}
+
+impl<C: ClientContext + 'static> DeadLetterProducer<C> {
+    pub fn new(producer: FutureProducer<C>, config: DeadLetterConfig) -> Self {
+        Self {
+            producer,
+            topic: config.topic,
+            failures: FailureWindow::new(config.max_failures, config.window),
+        }
+    }
+
+    /// Forwards `message` to the dead-letter topic, tagging it with its
+    /// original coordinates, `reason`, and an incremented
+    /// `convoy.dlq.retry_count` header.
+    ///
+    /// Returns `Ok(false)` without forwarding once the invalid-message-rate
+    /// policy has tripped, so the caller can stop pulling from the source
+    /// stream instead of continuing to flood the dead-letter topic.
+    async fn forward(
+        &self,
+        message: &BorrowedMessage<'_>,
+        reason: &str,
+    ) -> Result<bool, KafkaError> {
+        if self.failures.record() {
+            return Ok(false);
+        }
+
+        let retry_count = message
+            .headers()
+            .and_then(|headers| {
+                headers.iter().find_map(|header| {
+                    (header.key == DLQ_RETRY_COUNT_HEADER)
+                        .then_some(header.value)
+                        .flatten()
+                        .and_then(|value| std::str::from_utf8(value).ok())
+                        .and_then(|value| value.parse::<u64>().ok())
+                })
+            })
+            .unwrap_or(0)
+            + 1;
+
+        let partition = message.partition().to_string();
+        let offset = message.offset().to_string();
+        let retry_count = retry_count.to_string();
+
+        let mut headers = OwnedHeaders::new_with_capacity(5)
+            .insert(Header {
+                key: DLQ_ORIGINAL_TOPIC_HEADER,
+                value: Some(message.topic()),
+            })
+            .insert(Header {
+                key: DLQ_ORIGINAL_PARTITION_HEADER,
+                value: Some(&partition),
+            })
+            .insert(Header {
+                key: DLQ_ORIGINAL_OFFSET_HEADER,
+                value: Some(&offset),
+            })
+            .insert(Header {
+                key: DLQ_REASON_HEADER,
+                value: Some(reason),
+            })
+            .insert(Header {
+                key: DLQ_RETRY_COUNT_HEADER,
+                value: Some(&retry_count),
+            });
+
+        if let Some(original) = message.headers() {
+            for header in original.iter() {
+                if header.key == DLQ_RETRY_COUNT_HEADER {
+                    continue;
+                }
+
+                headers = headers.insert(Header {
+                    key: header.key,
+                    value: header.value,
+                });
+            }
+        }
+
+        let record = FutureRecord::to(&self.topic)
+            .key(message.key().unwrap_or_default())
+            .headers(headers)
+            .payload(message.payload().unwrap_or_default());
+
+        self.producer
+            .send(record, Duration::from_secs(10))
+            .await
+            .map_err(|err| err.0)?;
+
+        Ok(true)
+    }
+
+    fn is_tripped(&self) -> bool {
+        self.failures.is_tripped()
+    }
+}
+
+/// Errors surfaced by [`RdKafkaMessageStream`].
+#[derive(Debug)]
+pub enum KafkaConsumerError {
+    /// The underlying rdkafka client returned an error.
+    Kafka(KafkaError),
+    /// The dead-letter invalid-message-rate policy tripped: too many
+    /// messages were rejected within the configured window, so the stream
+    /// was stopped instead of flooding the dead-letter topic.
+    DeadLetterRateExceeded,
+}
+
+impl Display for KafkaConsumerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Kafka(err) => Display::fmt(err, f),
+            Self::DeadLetterRateExceeded => {
+                write!(
+                    f,
+                    "dead-letter invalid-message rate exceeded, stream stopped"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for KafkaConsumerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Kafka(err) => Some(err),
+            Self::DeadLetterRateExceeded => None,
+        }
+    }
+}
+
+/// Offset-commit strategy for a [`KafkaConsumer`].
+#[derive(Debug, Clone)]
+pub enum CommitConfig {
+    /// Commit with `CommitMode::Async` on every `ack`. Chatty under high
+    /// load, but this is the default, matching the crate's prior behavior.
+    PerMessage,
+    /// Store offsets locally via librdkafka's offset store on `ack`, and
+    /// only commit them to the broker once `max_batch` acks have
+    /// accumulated or on the first ack after `interval` has elapsed since
+    /// the last commit, plus once more on stream shutdown. The interval is
+    /// only checked when a message is settled; no timer commits offsets
+    /// while no messages are being settled.
+    Batched {
+        max_batch: usize,
+        interval: Duration,
+    },
+}
+
+impl Default for CommitConfig {
+    fn default() -> Self {
+        Self::PerMessage
+    }
+}
+
+/// Per-partition tracker for the highest *contiguously* acked offset, so a
+/// batched-mode consumer never stores an offset past an un-acked message.
+#[derive(Default)]
+struct PartitionOffsets {
+    pending: BTreeSet<i64>,
+    highest_contiguous: Option<i64>,
+}
+
+impl PartitionOffsets {
+    /// Records `offset` as acked, returning the new highest contiguously
+    /// acked offset if it advanced.
+    fn ack(&mut self, offset: i64) -> Option<i64> {
+        self.pending.insert(offset);
+
+        let mut next = match self.highest_contiguous {
+            Some(committed) => committed + 1,
+            None => *self.pending.iter().next().unwrap(),
+        };
+
+        let mut advanced = None;
+        while self.pending.remove(&next) {
+            self.highest_contiguous = Some(next);
+            advanced = Some(next);
+            next += 1;
+        }
+
+        advanced
+    }
+}
+
+/// Shared state backing [`CommitConfig::Batched`]: tracks per-partition
+/// contiguous offsets and decides when a batch is due to be flushed.
+struct BatchCommitState {
+    max_batch: usize,
+    interval: Duration,
+    partitions: Mutex<HashMap<(String, i32), PartitionOffsets>>,
+    acks_since_flush: AtomicUsize,
+    last_flush: Mutex<Instant>,
+}
+
+impl BatchCommitState {
+    fn new(max_batch: usize, interval: Duration) -> Self {
+        Self {
+            max_batch,
+            interval,
+            partitions: Mutex::new(HashMap::new()),
+            acks_since_flush: AtomicUsize::new(0),
+            last_flush: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Records `offset` as acked for `(topic, partition)`, returning the
+    /// new highest contiguously acked offset for that partition if it
+    /// advanced, so the caller can store it with librdkafka.
+    fn ack(&self, topic: &str, partition: i32, offset: i64) -> Option<i64> {
+        self.partitions
+            .lock()
+            .unwrap()
+            .entry((topic.to_string(), partition))
+            .or_default()
+            .ack(offset)
+    }
+
+    /// Returns `true` once `max_batch` acks have accumulated since the
+    /// last flush, or `interval` has elapsed since then.
+    fn should_flush(&self) -> bool {
+        let batch_full = self.acks_since_flush.fetch_add(1, Ordering::SeqCst) + 1 >= self.max_batch;
+        let timed_out = self.last_flush.lock().unwrap().elapsed() >= self.interval;
+
+        batch_full || timed_out
+    }
+
+    fn reset(&self) {
+        self.acks_since_flush.store(0, Ordering::SeqCst);
+        *self.last_flush.lock().unwrap() = Instant::now();
+    }
+}
+
 pub struct KafkaConsumer<C>
 where
     C: ConsumerContext + 'static,
 {
     consumer: Arc<StreamConsumer<C>>,
+    dead_letter: Option<Arc<DeadLetterProducer<C>>>,
+    commit: CommitConfig,
+    metrics: Arc<dyn Metrics>,
+    lag_sample_interval: Option<Duration>,
 }
 
 impl<C> KafkaConsumer<C>
@@ -130,19 +618,75 @@ where
     pub fn new(consumer: StreamConsumer<C>) -> Self {
         Self {
             consumer: Arc::new(consumer),
+            dead_letter: None,
+            commit: CommitConfig::default(),
+            metrics: Arc::new(NoopMetrics),
+            lag_sample_interval: None,
         }
     }
+
+    /// Routes messages that are `nack`ed or `reject`ed to a dead-letter
+    /// topic instead of silently dropping them.
+    pub fn with_dead_letter(mut self, dead_letter: DeadLetterProducer<C>) -> Self {
+        self.dead_letter = Some(Arc::new(dead_letter));
+        self
+    }
+
+    /// Overrides the offset-commit strategy. Defaults to
+    /// [`CommitConfig::PerMessage`].
+    pub fn with_commit_config(mut self, commit: CommitConfig) -> Self {
+        self.commit = commit;
+        self
+    }
+
+    /// Attaches a [`Metrics`] sink to report message throughput.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Enables periodic consumer-lag sampling (`high_watermark - offset`)
+    /// through the attached [`Metrics`] sink, every `interval`.
+    pub fn with_lag_sampling(mut self, interval: Duration) -> Self {
+        self.lag_sample_interval = Some(interval);
+        self
+    }
 }
 
 #[async_trait]
 impl<C: ConsumerContext + 'static> MessageBus for KafkaConsumer<C> {
     type IncomingMessage = RdKafkaOwnedMessage<C>;
-    type Error = rdkafka::error::KafkaError;
+    type Error = KafkaConsumerError;
     type Stream = RdKafkaMessageStream<C>;
 
     async fn into_stream(self) -> Result<Self::Stream, Self::Error> {
+        let commit = match self.commit {
+            CommitConfig::PerMessage => None,
+            CommitConfig::Batched {
+                max_batch,
+                interval,
+            } => Some(Arc::new(BatchCommitState::new(max_batch, interval))),
+        };
+
+        let lag_sampler = self.lag_sample_interval.map(|interval| {
+            LagSampler::spawn(
+                Arc::clone(&self.consumer),
+                Arc::clone(&self.metrics),
+                interval,
+            )
+        });
+
         let stream = self.consumer.stream();
-        let stream = unsafe { RdKafkaMessageStream::new(&self.consumer, stream) };
+        let stream = unsafe {
+            RdKafkaMessageStream::new(
+                &self.consumer,
+                stream,
+                self.dead_letter,
+                commit,
+                self.metrics,
+                lag_sampler,
+            )
+        };
 
         Ok(stream)
     }
@@ -181,24 +725,46 @@ impl<C: ConsumerContext + 'static> IncomingMessage for RdKafkaOwnedMessage<C> {
     }
 
     async fn ack(&self) -> Result<(), Self::Error> {
-        self.consumer
-            .commit_message(&self.message, CommitMode::Async)
+        self.settle_offset()?;
+        self.metrics
+            .message_settled(self.message().topic(), Outcome::Success);
+
+        Ok(())
     }
 
     async fn nack(&self) -> Result<(), Self::Error> {
+        self.metrics
+            .message_settled(self.message().topic(), Outcome::Failure);
+
+        let Some(dead_letter) = self.dead_letter.as_ref() else {
+            return Ok(());
+        };
+
+        if dead_letter.forward(self.message(), "nack").await? {
+            self.settle_offset()?;
+        }
+
         Ok(())
     }
 
     async fn reject(&self) -> Result<(), Self::Error> {
-        self.consumer
-            .commit_message(&self.message, CommitMode::Async)
+        self.metrics
+            .message_settled(self.message().topic(), Outcome::Failure);
+
+        if let Some(dead_letter) = self.dead_letter.as_ref() {
+            if !dead_letter.forward(self.message(), "reject").await? {
+                return Ok(());
+            }
+        }
+
+        self.settle_offset()
     }
 
     fn make_span(&self) -> tracing::Span {
         let msg = self.message();
 
         // https://opentelemetry.io/docs/specs/otel/trace/semantic_conventions/messaging/#apache-kafka
-        tracing::info_span!(
+        let span = tracing::info_span!(
             "consumer",
             otel.name = %format!("{} receive", msg.topic()).as_str(),
             otel.kind = "CONSUMER",
@@ -210,7 +776,48 @@ impl<C: ConsumerContext + 'static> IncomingMessage for RdKafkaOwnedMessage<C> {
             messaging.kafka.message.key = msg.key().and_then(|k| std::str::from_utf8(k).ok()).unwrap_or_default(),
             messaging.kafka.message.offset = msg.offset(),
             convoy.kind = tracing::field::Empty,
-        )
+        );
+
+        // Link this span to whatever span injected a `traceparent` into
+        // the message's headers on produce, so traces stay connected
+        // across the broker.
+        let parent_cx = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&RdKafkaHeaderExtractor(msg.headers()))
+        });
+        span.set_parent(parent_cx);
+
+        span
+    }
+}
+
+/// Reads W3C trace-context headers (`traceparent`/`tracestate`) straight off
+/// an [`rdkafka`] message, for [`opentelemetry`] propagator extraction.
+struct RdKafkaHeaderExtractor<'a>(Option<&'a rdkafka::message::BorrowedHeaders>);
+
+impl<'a> Extractor for RdKafkaHeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0?
+            .iter()
+            .find(|header| header.key == key)
+            .and_then(|header| header.value)
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0
+            .map(|headers| headers.iter().map(|header| header.key).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Writes W3C trace-context headers (`traceparent`/`tracestate`) into the
+/// [`RawHeaders`] a [`KafkaProducer`] is about to send, for
+/// [`opentelemetry`] propagator injection.
+struct RawHeadersInjector<'a>(&'a mut RawHeaders);
+
+impl<'a> Injector for RawHeadersInjector<'a> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_string(), value);
     }
 }
 
@@ -242,11 +849,22 @@ impl KafkaProducerOptions {
 pub struct KafkaProducer<C: ClientContext + 'static> {
     producer: FutureProducer<C>,
     topic: String,
+    metrics: Arc<dyn Metrics>,
 }
 
 impl<C: ClientContext + 'static> KafkaProducer<C> {
     pub fn new(producer: FutureProducer<C>, topic: String) -> Self {
-        Self { producer, topic }
+        Self {
+            producer,
+            topic,
+            metrics: Arc::new(NoopMetrics),
+        }
+    }
+
+    /// Attaches a [`Metrics`] sink to report send throughput and latency.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
     }
 }
 
@@ -268,6 +886,16 @@ impl<C: ClientContext + 'static> Producer for KafkaProducer<C> {
             additional_headers,
         } = options;
 
+        // Inject the current span's W3C trace-context into the outgoing
+        // headers before they are folded into `OwnedHeaders`, so the
+        // consumer span can be linked as its child.
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(
+                &tracing::Span::current().context(),
+                &mut RawHeadersInjector(&mut headers),
+            );
+        });
+
         headers.extend(additional_headers);
 
         let topic = topic_override.as_deref().unwrap_or(self.topic.as_str());
@@ -288,11 +916,22 @@ impl<C: ClientContext + 'static> Producer for KafkaProducer<C> {
             .headers(headers)
             .payload(&payload);
 
-        self.producer
+        let started = Instant::now();
+        let result = self
+            .producer
             .send(record, Duration::from_secs(10))
             .await
             .map(|_| ())
-            .map_err(|err| err.0)
+            .map_err(|err| err.0);
+
+        let outcome = if result.is_ok() {
+            Outcome::Success
+        } else {
+            Outcome::Failure
+        };
+        self.metrics.message_sent(topic, outcome, started.elapsed());
+
+        result
     }
 
     fn make_span(
@@ -319,3 +958,48 @@ impl<C: ClientContext + 'static> Producer for KafkaProducer<C> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partition_offsets_only_advance_on_contiguous_acks() {
+        let mut offsets = PartitionOffsets::default();
+
+        assert_eq!(offsets.ack(5), Some(5));
+        // 6 is missing, so this ack can't advance the contiguous cursor yet.
+        assert_eq!(offsets.ack(7), None);
+        // Filling the gap jumps the cursor past the offset acked out of order.
+        assert_eq!(offsets.ack(6), Some(7));
+    }
+
+    #[test]
+    fn batch_commit_state_flushes_after_max_batch_acks() {
+        let state = BatchCommitState::new(2, Duration::from_secs(60));
+
+        assert!(!state.should_flush());
+        assert!(state.should_flush());
+    }
+
+    #[test]
+    fn failure_window_trips_after_more_than_max_failures() {
+        let window = FailureWindow::new(2, Duration::from_secs(60));
+
+        assert!(!window.record());
+        assert!(!window.record());
+        assert!(window.record());
+        assert!(window.is_tripped());
+    }
+
+    #[test]
+    fn failure_window_evicts_failures_older_than_the_window() {
+        let window = FailureWindow::new(1, Duration::from_millis(20));
+
+        assert!(!window.record());
+        std::thread::sleep(Duration::from_millis(40));
+        // The first failure has aged out, so this one alone shouldn't trip
+        // a window configured for 1 failure.
+        assert!(!window.record());
+    }
+}
@@ -0,0 +1,370 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use async_trait::async_trait;
+use futures_lite::Stream;
+
+use crate::{
+    consumer::{IncomingMessage, MessageBus},
+    message::RawHeaders,
+    producer::Producer,
+};
+
+#[derive(Clone)]
+struct StoredMessage {
+    key: Option<Vec<u8>>,
+    headers: RawHeaders,
+    payload: Vec<u8>,
+}
+
+#[derive(Default)]
+struct Topic {
+    messages: Vec<StoredMessage>,
+    wakers: Vec<Waker>,
+}
+
+impl Topic {
+    fn wake_all(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// An in-memory stand-in for a Kafka cluster, shared between [`LocalProducer`]s
+/// and [`LocalConsumer`]s in tests.
+///
+/// Each topic is an append-only log. Every consumer group tracks its own
+/// cursor into that log, so messages produced to a topic become visible to
+/// every group subscribed to it, independently.
+#[derive(Default)]
+pub struct LocalBroker {
+    topics: Mutex<HashMap<String, Topic>>,
+    offsets: Mutex<HashMap<(String, String), usize>>,
+}
+
+impl LocalBroker {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    fn produce(&self, topic: &str, message: StoredMessage) {
+        let mut topics = self.topics.lock().unwrap();
+        topics
+            .entry(topic.to_string())
+            .or_default()
+            .messages
+            .push(message);
+        topics.get_mut(topic).unwrap().wake_all();
+    }
+
+    fn poll_message(
+        &self,
+        topic: &str,
+        group: &str,
+        cx: &mut Context<'_>,
+    ) -> Poll<(usize, StoredMessage)> {
+        let mut topics = self.topics.lock().unwrap();
+        let entry = topics.entry(topic.to_string()).or_default();
+
+        let offset = *self
+            .offsets
+            .lock()
+            .unwrap()
+            .get(&(topic.to_string(), group.to_string()))
+            .unwrap_or(&0);
+
+        match entry.messages.get(offset) {
+            Some(message) => Poll::Ready((offset, message.clone())),
+            None => {
+                entry.wakers.push(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    fn commit(&self, topic: &str, group: &str, offset: usize) {
+        self.offsets
+            .lock()
+            .unwrap()
+            .insert((topic.to_string(), group.to_string()), offset);
+    }
+}
+
+/// A [`MessageBus`] backed by a [`LocalBroker`] instead of a live Kafka
+/// cluster, so handlers can be tested without a broker.
+pub struct LocalConsumer {
+    broker: Arc<LocalBroker>,
+    topic: String,
+    group: String,
+}
+
+impl LocalConsumer {
+    pub fn new(
+        broker: Arc<LocalBroker>,
+        topic: impl Into<String>,
+        group: impl Into<String>,
+    ) -> Self {
+        Self {
+            broker,
+            topic: topic.into(),
+            group: group.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageBus for LocalConsumer {
+    type IncomingMessage = LocalMessage;
+    type Error = Infallible;
+    type Stream = LocalMessageStream;
+
+    async fn into_stream(self) -> Result<Self::Stream, Self::Error> {
+        Ok(LocalMessageStream {
+            broker: self.broker,
+            topic: self.topic,
+            group: self.group,
+        })
+    }
+}
+
+pub struct LocalMessageStream {
+    broker: Arc<LocalBroker>,
+    topic: String,
+    group: String,
+}
+
+impl Stream for LocalMessageStream {
+    type Item = Result<LocalMessage, Infallible>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.broker
+            .poll_message(&self.topic, &self.group, cx)
+            .map(|(offset, stored)| {
+                Some(Ok(LocalMessage {
+                    broker: Arc::clone(&self.broker),
+                    topic: self.topic.clone(),
+                    group: self.group.clone(),
+                    offset,
+                    stored,
+                }))
+            })
+    }
+}
+
+pub struct LocalMessage {
+    broker: Arc<LocalBroker>,
+    topic: String,
+    group: String,
+    offset: usize,
+    stored: StoredMessage,
+}
+
+#[async_trait]
+impl IncomingMessage for LocalMessage {
+    type Error = Infallible;
+
+    fn headers(&self) -> RawHeaders {
+        self.stored.headers.clone()
+    }
+
+    fn payload(&self) -> &[u8] {
+        &self.stored.payload
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        self.stored.key.as_deref()
+    }
+
+    async fn ack(&self) -> Result<(), Self::Error> {
+        self.broker
+            .commit(&self.topic, &self.group, self.offset + 1);
+        Ok(())
+    }
+
+    async fn nack(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn reject(&self) -> Result<(), Self::Error> {
+        self.broker
+            .commit(&self.topic, &self.group, self.offset + 1);
+        Ok(())
+    }
+
+    fn make_span(&self) -> tracing::Span {
+        // https://opentelemetry.io/docs/specs/otel/trace/semantic_conventions/messaging/#apache-kafka
+        tracing::info_span!(
+            "consumer",
+            otel.name = %format!("{} receive", self.topic).as_str(),
+            otel.kind = "CONSUMER",
+            otel.status_code = tracing::field::Empty,
+            messaging.system = "local",
+            messaging.operation = "receive",
+            messaging.message.payload_size_bytes = self.stored.payload.len(),
+            messaging.kafka.message.offset = self.offset as i64,
+            convoy.kind = tracing::field::Empty,
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct LocalProducerOptions {
+    topic_override: Option<String>,
+}
+
+impl LocalProducerOptions {
+    pub fn override_topic(self, topic: String) -> Self {
+        Self {
+            topic_override: Some(topic),
+        }
+    }
+}
+
+/// A [`Producer`] backed by a [`LocalBroker`] instead of a live Kafka
+/// cluster, so handlers can be tested without a broker.
+#[derive(Clone)]
+pub struct LocalProducer {
+    broker: Arc<LocalBroker>,
+    topic: String,
+}
+
+impl LocalProducer {
+    pub fn new(broker: Arc<LocalBroker>, topic: impl Into<String>) -> Self {
+        Self {
+            broker,
+            topic: topic.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Producer for LocalProducer {
+    type Options = LocalProducerOptions;
+
+    type Error = Infallible;
+
+    async fn send(
+        &self,
+        key: String,
+        headers: RawHeaders,
+        payload: Vec<u8>,
+        options: Self::Options,
+    ) -> Result<(), Self::Error> {
+        let topic = options.topic_override.unwrap_or_else(|| self.topic.clone());
+
+        self.broker.produce(
+            &topic,
+            StoredMessage {
+                key: Some(key.into_bytes()),
+                headers,
+                payload,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn make_span(
+        &self,
+        key: &str,
+        _headers: &RawHeaders,
+        _payload: &[u8],
+        options: &Self::Options,
+    ) -> tracing::Span {
+        let topic = options
+            .topic_override
+            .as_deref()
+            .unwrap_or(self.topic.as_str());
+
+        tracing::info_span!(
+            "producer",
+            otel.name = %format!("{} send", topic).as_str(),
+            otel.kind = "PRODUCER",
+            otel.status_code = tracing::field::Empty,
+            messaging.system = "local",
+            messaging.destination = %topic,
+            messaging.destination_kind = "topic",
+            messaging.kafka.message_key = key,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_lite::{future::block_on, StreamExt};
+
+    use super::*;
+
+    fn stored(payload: &[u8]) -> StoredMessage {
+        StoredMessage {
+            key: None,
+            headers: RawHeaders::default(),
+            payload: payload.to_vec(),
+        }
+    }
+
+    #[test]
+    fn ack_advances_the_cursor_past_the_acked_message() {
+        let broker = LocalBroker::new();
+        broker.produce("topic", stored(b"one"));
+        broker.produce("topic", stored(b"two"));
+
+        let mut stream =
+            block_on(LocalConsumer::new(Arc::clone(&broker), "topic", "group").into_stream())
+                .unwrap();
+
+        let first = block_on(stream.next()).unwrap().unwrap();
+        assert_eq!(first.payload(), b"one");
+        block_on(first.ack()).unwrap();
+
+        let second = block_on(stream.next()).unwrap().unwrap();
+        assert_eq!(second.payload(), b"two");
+    }
+
+    #[test]
+    fn nack_does_not_advance_the_cursor() {
+        let broker = LocalBroker::new();
+        broker.produce("topic", stored(b"one"));
+
+        let mut stream =
+            block_on(LocalConsumer::new(Arc::clone(&broker), "topic", "group").into_stream())
+                .unwrap();
+
+        let first = block_on(stream.next()).unwrap().unwrap();
+        block_on(first.nack()).unwrap();
+
+        // A fresh consumer in the same group re-reads from the start,
+        // since `nack` never advanced the group's cursor.
+        let mut stream =
+            block_on(LocalConsumer::new(Arc::clone(&broker), "topic", "group").into_stream())
+                .unwrap();
+        let redelivered = block_on(stream.next()).unwrap().unwrap();
+        assert_eq!(redelivered.payload(), b"one");
+    }
+
+    #[test]
+    fn local_producer_send_is_visible_to_local_consumer() {
+        let broker = LocalBroker::new();
+        let producer = LocalProducer::new(Arc::clone(&broker), "topic");
+
+        block_on(producer.send(
+            "key".to_string(),
+            RawHeaders::default(),
+            b"payload".to_vec(),
+            LocalProducerOptions::default(),
+        ))
+        .unwrap();
+
+        let mut stream =
+            block_on(LocalConsumer::new(Arc::clone(&broker), "topic", "group").into_stream())
+                .unwrap();
+        let received = block_on(stream.next()).unwrap().unwrap();
+        assert_eq!(received.payload(), b"payload");
+    }
+}
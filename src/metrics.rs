@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+/// Whether an instrumented operation succeeded or failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    Failure,
+}
+
+impl Outcome {
+    pub fn as_tag(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+/// Pluggable metrics sink for consumer/producer throughput and lag.
+///
+/// Implement this to wire the crate's instrumentation into your metrics
+/// backend of choice. [`NoopMetrics`] is used when no sink is attached;
+/// [`StatsdMetrics`] is a ready-to-use statsd-style implementation.
+pub trait Metrics: Send + Sync {
+    /// A message was yielded by a consumer stream.
+    fn message_received(&self, topic: &str) {
+        let _ = topic;
+    }
+
+    /// A consumed message was `ack`ed, `nack`ed, or `reject`ed.
+    fn message_settled(&self, topic: &str, outcome: Outcome) {
+        let _ = (topic, outcome);
+    }
+
+    /// A producer `send` completed, after `latency`.
+    fn message_sent(&self, topic: &str, outcome: Outcome, latency: Duration) {
+        let _ = (topic, outcome, latency);
+    }
+
+    /// Consumer lag (`high_watermark - offset`) observed for a partition.
+    fn consumer_lag(&self, topic: &str, partition: i32, lag: i64) {
+        let _ = (topic, partition, lag);
+    }
+}
+
+/// Discards every metric. Used when no sink is attached.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {}
+
+/// Emits the crate's metrics as statsd packets over UDP.
+///
+/// Send failures (e.g. a full socket buffer) are swallowed, since losing a
+/// metric is preferable to slowing down message processing.
+pub struct StatsdMetrics {
+    socket: std::net::UdpSocket,
+    prefix: String,
+}
+
+impl StatsdMetrics {
+    pub fn new(
+        addr: impl std::net::ToSocketAddrs,
+        prefix: impl Into<String>,
+    ) -> std::io::Result<Self> {
+        let socket = std::net::UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(addr)?;
+
+        Ok(Self {
+            socket,
+            prefix: prefix.into(),
+        })
+    }
+
+    fn emit(&self, metric: &str) {
+        let _ = self.socket.send(metric.as_bytes());
+    }
+}
+
+impl Metrics for StatsdMetrics {
+    fn message_received(&self, topic: &str) {
+        self.emit(&format!(
+            "{}.messages_received:1|c|#topic:{topic}",
+            self.prefix
+        ));
+    }
+
+    fn message_settled(&self, topic: &str, outcome: Outcome) {
+        self.emit(&format!(
+            "{}.messages_settled:1|c|#topic:{topic},outcome:{}",
+            self.prefix,
+            outcome.as_tag()
+        ));
+    }
+
+    fn message_sent(&self, topic: &str, outcome: Outcome, latency: Duration) {
+        self.emit(&format!(
+            "{}.send_latency_ms:{}|ms|#topic:{topic},outcome:{}",
+            self.prefix,
+            latency.as_millis(),
+            outcome.as_tag()
+        ));
+    }
+
+    fn consumer_lag(&self, topic: &str, partition: i32, lag: i64) {
+        self.emit(&format!(
+            "{}.consumer_lag:{lag}|g|#topic:{topic},partition:{partition}",
+            self.prefix
+        ));
+    }
+}